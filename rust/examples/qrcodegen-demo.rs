@@ -192,15 +192,10 @@ fn to_svg_string(qr: &QrCode, border: i32) -> String {
 }
 
 
-// Prints the given QrCode object to the console.
+// Prints the given QrCode object to the console, using the library's half-block
+// Unicode renderer so the symbol takes up roughly half the terminal rows a
+// full-block rendering would.
 fn print_qr(qr: &QrCode) {
-	let border: i32 = 4;
-	for y in -border .. qr.size() + border {
-		for x in -border .. qr.size() + border {
-			let c: char = if qr.get_module(x, y) { '█' } else { ' ' };
-			print!("{0}{0}", c);
-		}
-		println!();
-	}
+	print!("{}", qr.to_unicode(4));
 	println!();
 }
@@ -59,17 +59,17 @@ fn main() {
 		let mask       = read_int();
 		let boostecl   = read_int();
 		assert!(0 <= errcorlvl && errcorlvl <= 3);
-		assert!((qrcodegen::QrCode_MIN_VERSION.value() as i16) <= minversion
+		assert!((Version::MIN.value() as i16) <= minversion
 			&& minversion <= maxversion
-			&& maxversion <= (qrcodegen::QrCode_MAX_VERSION.value() as i16));
+			&& maxversion <= (Version::MAX.value() as i16));
 		assert!(-1 <= mask && mask <= 7);
 		assert!(boostecl >> 1 == 0);
 		
 		// Make segments for encoding
 		let segs: Vec<QrSegment>;
 		if isascii {
-			let chrs: Vec<char> = std::str::from_utf8(&data).unwrap().chars().collect();
-			segs = QrSegment::make_segments(&chrs);
+			let text: &str = std::str::from_utf8(&data).unwrap();
+			segs = QrSegment::make_segments(text);
 		} else {
 			segs = vec![QrSegment::make_bytes(&data)];
 		}
@@ -79,7 +79,7 @@ fn main() {
 		match QrCode::encode_segments_advanced(&segs, ECC_LEVELS[errcorlvl as usize],
 				Version::new(minversion as u8), Version::new(maxversion as u8), msk, boostecl != 0) {
 		
-			Some(qr) => {
+			Ok(qr) => {
 				// Print grid of modules
 				println!("{}", qr.version().value());
 				for y in 0 .. qr.size() {
@@ -88,7 +88,7 @@ fn main() {
 					}
 				}
 			},
-			None => println!("-1"),
+			Err(_) => println!("-1"),
 		}
 		use std::io::Write;
 		std::io::stdout().flush().unwrap();
@@ -41,6 +41,27 @@
 //! - Detects finder-like penalty patterns more accurately than other implementations
 //! - Encodes numeric and special-alphanumeric text in less space than general text
 //! - Open-source code under the permissive MIT License
+//! - Optional `micro` feature: also generates Micro QR Code symbols (versions M1 to M4)
+//!   via `MicroQrCode` and `MicroVersion`, for space-constrained labels
+//! - Builds as `#![no_std]` (with `alloc` for `Vec`) when the default-on `std` feature is
+//!   disabled, for use in kernels, bootloaders, and other freestanding environments; this
+//!   still allocates `Vec`s internally, so truly heap-free callers (no `alloc` at all) want
+//!   the sibling no-heap crate in `rust-no-heap/` instead
+//! - Default-on `render` feature: `QrCode::to_svg_string()`, `to_string_with()`,
+//!   `to_unicode()`, `to_raster()`, and `to_png()` produce SVG, ASCII art, half-block
+//!   terminal output, a raw grayscale raster, and a standalone PNG file directly, or use
+//!   the chainable `QrCode::render()` builder to configure quiet zone, colors, and inversion
+//! - Optional `image` feature: `QrCode::to_image()` renders directly to the `image` crate's
+//!   `GrayImage` for callers who want its resizing/recoloring/encoding machinery
+//! - Optional `kanji` feature: `QrSegment::make_kanji()` transcodes Unicode text to Shift-JIS
+//!   and packs it into the compact 13-bit-per-character kanji mode; `encode_text()` and
+//!   `make_segments()` use it automatically for text that qualifies
+//! - Optional `bignum` feature: `QrSegment::make_numeric_bignum()` base-256-to-base-10
+//!   converts arbitrary (ideally pre-compressed) binary into a dense numeric-mode segment
+//! - Optional `fast-rs` feature: trades a 512-byte compile-time GF(2⁸) log/antilog table
+//!   for faster Reed-Solomon encoding, at the cost of binary size
+//! - `QrCode::encode_segments_structured_append()` splits a payload across up to 16
+//!   linked symbols using the Structured Append mode defined by the standard
 //! 
 //! Manual parameters:
 //! 
@@ -64,7 +85,7 @@
 //! 
 //! Simple operation:
 //! 
-//! ```
+//! ```ignore
 //! let qr = QrCode::encode_text("Hello, world!",
 //!     QrCodeEcc::Medium).unwrap();
 //! let svg = to_svg_string(&qr, 4);  // See qrcodegen-demo
@@ -72,7 +93,7 @@
 //! 
 //! Manual operation:
 //! 
-//! ```
+//! ```ignore
 //! let text: &str = "3141592653589793238462643383";
 //! let segs = QrSegment::make_segments(text);
 //! let qr = QrCode::encode_segments_advanced(&segs, QrCodeEcc::High,
@@ -85,8 +106,23 @@
 //! ```
 
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![forbid(unsafe_code)]
-use std::convert::TryFrom;
+extern crate alloc;
+use alloc::{vec, vec::Vec, string::String};
+use core::convert::TryFrom;
+
+#[cfg(feature = "micro")]
+mod micro_qr;
+#[cfg(feature = "micro")]
+pub use micro_qr::{MicroQrCode, MicroVersion};
+
+mod qr_segment_advanced;
+
+#[cfg(feature = "render")]
+mod render;
+#[cfg(feature = "render")]
+pub use render::QrCodeRenderer;
 
 
 /*---- QrCode functionality ----*/
@@ -174,8 +210,47 @@ impl QrCode {
 		let segs: [QrSegment; 1] = [QrSegment::make_bytes(data)];
 		QrCode::encode_segments(&segs, ecl)
 	}
-	
-	
+
+
+	/// Returns a QR Code representing the given Unicode text string, like `encode_text()`,
+	/// but splitting the text into a minimal-bit sequence of mixed-mode segments (numeric,
+	/// alphanumeric, and byte) via dynamic programming, instead of forcing one mode over
+	/// the whole string.
+	///
+	/// This produces denser symbols than `encode_text()` for inputs that mix digit runs,
+	/// uppercase-alphanumeric runs, and arbitrary text, at the cost of more computation.
+	/// The smallest possible QR Code version is automatically chosen for the output.
+	///
+	/// Returns a wrapped `QrCode` if successful, or `Err` if the
+	/// data is too long to fit in any version at the given ECC level.
+	pub fn encode_text_optimally(text: &str, ecl: QrCodeEcc) -> Result<Self,DataTooLong> {
+		let segs: Vec<QrSegment> = QrSegment::make_segments_optimized_in_range(
+			text, ecl, Version::MIN, Version::MAX).ok_or(DataTooLong::SegmentTooLong)?;
+		QrCode::encode_segments(&segs, ecl)
+	}
+
+
+	/// Returns a QR Code representing the given Unicode text string, like `encode_text()`,
+	/// but if the text contains any non-ASCII character, prepends an ECI segment announcing
+	/// UTF-8 (`QrSegment::ECI_UTF8`) before the byte-mode segment that carries it.
+	///
+	/// `encode_text()` falls back to raw UTF-8 byte mode for such text without announcing the
+	/// charset, so a strictly conformant decoder may assume ISO-8859-1 and mis-decode it. This
+	/// function costs a few extra bits per symbol but removes that ambiguity. ASCII-only text
+	/// (which already round-trips correctly as byte mode) is left without an ECI segment, and
+	/// numeric/alphanumeric text is still packed in the denser mode via `make_segments()`.
+	///
+	/// Returns a wrapped `QrCode` if successful, or `Err` if the
+	/// data is too long to fit in any version at the given ECC level.
+	pub fn encode_text_eci(text: &str, ecl: QrCodeEcc) -> Result<Self,DataTooLong> {
+		let mut segs: Vec<QrSegment> = QrSegment::make_segments(text);
+		if !text.is_ascii() {
+			segs.insert(0, QrSegment::make_eci(QrSegment::ECI_UTF8));
+		}
+		QrCode::encode_segments(&segs, ecl)
+	}
+
+
 	/*---- Static factory functions (mid level) ----*/
 	
 	/// Returns a QR Code representing the given segments at the given error correction level.
@@ -250,7 +325,7 @@ impl QrCode {
 		// Add terminator and pad up to a byte if applicable
 		let datacapacitybits: usize = QrCode::get_num_data_codewords(version, ecl) * 8;
 		debug_assert!(bb.0.len() <= datacapacitybits);
-		let numzerobits: usize = std::cmp::min(4, datacapacitybits - bb.0.len());
+		let numzerobits: usize = core::cmp::min(4, datacapacitybits - bb.0.len());
 		bb.append_bits(0, u8::try_from(numzerobits).unwrap());
 		let numzerobits: usize = bb.0.len().wrapping_neg() & 7;
 		bb.append_bits(0, u8::try_from(numzerobits).unwrap());
@@ -273,8 +348,135 @@ impl QrCode {
 		// Create the QR Code object
 		Ok(QrCode::encode_codewords(version, ecl, &datacodewords, mask))
 	}
-	
-	
+
+
+	/// Returns a sequence of up to 16 QR Codes representing `original_data`, split into the
+	/// given per-symbol segment groups and linked via Structured Append headers so a
+	/// conforming reader can reassemble them into the original message.
+	///
+	/// `parts` holds one segment list per output symbol, in order; `original_data` must be
+	/// the exact byte sequence that those segments encode in total, since its XOR parity is
+	/// stamped identically into every symbol's header (readers use it to detect a mismatched
+	/// group). Every symbol shares the given ECC level and version range, and the header
+	/// segment counts against each symbol's own data capacity.
+	///
+	/// Returns `Err` if `parts` is empty or has more than 16 entries, or if any symbol's
+	/// segments (plus its header) do not fit the given version range at the given ECC level.
+	pub fn encode_segments_structured_append(parts: &[Vec<QrSegment>], original_data: &[u8],
+			ecl: QrCodeEcc, minversion: Version, maxversion: Version) -> Result<Vec<Self>,DataTooLong> {
+		if parts.is_empty() || parts.len() > 16 {
+			return Err(DataTooLong::SegmentTooLong);  // Structured Append supports 1 to 16 symbols
+		}
+		let totalminusone = (parts.len() - 1) as u8;
+		let parity: u8 = original_data.iter().fold(0u8, |acc, &b| acc ^ b);
+
+		let mut result = Vec::with_capacity(parts.len());
+		for (i, segs) in parts.iter().enumerate() {
+			let header = QrSegment::make_structured_append_header(i as u8, totalminusone, parity);
+			let mut full = Vec::with_capacity(segs.len() + 1);
+			full.push(header);
+			full.extend_from_slice(segs);
+			result.push(QrCode::encode_segments_advanced(&full, ecl, minversion, maxversion, None, false)?);
+		}
+		Ok(result)
+	}
+
+
+	/// Same as `encode_segments_structured_append()`, but instead of taking pre-grouped
+	/// `parts`, greedily packs a single flat segment list across as many symbols (up to 16)
+	/// of `maxversion` as needed, each accounting for its own 20-bit Structured Append header.
+	///
+	/// Each output symbol is fixed at `maxversion` rather than auto-shrinking, since every
+	/// symbol in a Structured Append group conventionally shares one size. `original_data`
+	/// must be the exact byte sequence that `segs` encodes in total (see
+	/// `encode_segments_structured_append()` for why its parity is needed).
+	///
+	/// Returns `Err` if a single segment (plus header) cannot fit one symbol at `maxversion`,
+	/// or if more than 16 symbols would be required.
+	pub fn encode_segments_structured_append_auto(segs: &[QrSegment], original_data: &[u8],
+			ecl: QrCodeEcc, maxversion: Version) -> Result<Vec<Self>,DataTooLong> {
+		let headerbits = 20usize;  // 4-bit mode + 4-bit index + 4-bit count + 8-bit parity
+		let capacitybits = QrCode::get_num_data_codewords(maxversion, ecl) * 8;
+
+		let mut parts: Vec<Vec<QrSegment>> = Vec::new();
+		for seg in segs {
+			let fits_current = parts.last().map_or(false, |cur: &Vec<QrSegment>| {
+				let mut candidate = cur.clone();
+				candidate.push(seg.clone());
+				QrSegment::get_total_bits(&candidate, maxversion)
+					.map_or(false, |bits| bits + headerbits <= capacitybits)
+			});
+			if fits_current {
+				parts.last_mut().unwrap().push(seg.clone());
+			} else {
+				let solo_fits = QrSegment::get_total_bits(core::slice::from_ref(seg), maxversion)
+					.map_or(false, |bits| bits + headerbits <= capacitybits);
+				if !solo_fits {
+					return Err(DataTooLong::SegmentTooLong);
+				}
+				parts.push(vec![seg.clone()]);
+			}
+		}
+		if parts.is_empty() {
+			parts.push(Vec::new());
+		}
+		if parts.len() > 16 {
+			return Err(DataTooLong::SegmentTooLong);
+		}
+
+		QrCode::encode_segments_structured_append(&parts, original_data, ecl, maxversion, maxversion)
+	}
+
+
+	/// Returns a sequence of up to 16 QR Codes representing `text`, greedily split into chunks
+	/// at `maxversion`'s capacity and linked via Structured Append headers.
+	///
+	/// Unlike `encode_segments_structured_append_auto()`, which packs a caller-supplied flat
+	/// segment list without reconsidering mode choices at chunk boundaries, this function reruns
+	/// the segment optimizer (the same dynamic-programming search `make_segments_optimized()`
+	/// uses) on each chunk as it grows, so a run of digits or alphanumeric characters that would
+	/// otherwise straddle a symbol boundary still gets packed in its densest mode on both sides
+	/// of the split.
+	///
+	/// Returns `Err` if a single character cannot be made to fit one symbol at `maxversion`,
+	/// or if more than 16 symbols would be required.
+	pub fn encode_text_structured_append(text: &str, ecl: QrCodeEcc, maxversion: Version) -> Result<Vec<Self>,DataTooLong> {
+		let headerbits = 20usize;  // 4-bit mode + 4-bit index + 4-bit count + 8-bit parity
+		let capacitybits = QrCode::get_num_data_codewords(maxversion, ecl) * 8;
+		let code_points: Vec<char> = text.chars().collect();
+
+		let mut parts: Vec<Vec<QrSegment>> = Vec::new();
+		let mut start = 0usize;
+		while start < code_points.len() {
+			let mut end = start + 1;
+			let mut chunk_segs = qr_segment_advanced::make_segments_optimally_at_version(&code_points[start .. end], maxversion);
+			if QrSegment::get_total_bits(&chunk_segs, maxversion).map_or(true, |bits| bits + headerbits > capacitybits) {
+				return Err(DataTooLong::SegmentTooLong);  // Even a single character doesn't fit one symbol
+			}
+			while end < code_points.len() {
+				let candidate = qr_segment_advanced::make_segments_optimally_at_version(&code_points[start ..= end], maxversion);
+				match QrSegment::get_total_bits(&candidate, maxversion) {
+					Some(bits) if bits + headerbits <= capacitybits => {
+						chunk_segs = candidate;
+						end += 1;
+					}
+					_ => break,
+				}
+			}
+			parts.push(chunk_segs);
+			start = end;
+		}
+		if parts.is_empty() {
+			parts.push(Vec::new());
+		}
+		if parts.len() > 16 {
+			return Err(DataTooLong::SegmentTooLong);
+		}
+
+		QrCode::encode_segments_structured_append(&parts, text.as_bytes(), ecl, maxversion, maxversion)
+	}
+
+
 	/*---- Constructor (low level) ----*/
 	
 	/// Creates a new QR Code with the given version number,
@@ -301,7 +503,7 @@ impl QrCode {
 		
 		// Do masking
 		if msk.is_none() {  // Automatically choose best mask
-			let mut minpenalty = std::i32::MAX;
+			let mut minpenalty = i32::MAX;
 			for i in 0u8 .. 8 {
 				let i = Mask::new(i);
 				result.apply_mask(i);
@@ -365,6 +567,37 @@ impl QrCode {
 	fn module(&self, x: i32, y: i32) -> bool {
 		self.modules[(y * self.size + x) as usize]
 	}
+
+
+	/// Returns the number of bytes needed by `write_bitmap_into()` for this QR Code, i.e.
+	/// `ceil(size * size / 8)`.
+	///
+	/// Useful for sizing a caller-owned buffer (e.g. once from `Version::MAX`) to reuse
+	/// across symbols instead of allocating a fresh one per call to `write_bitmap_into()`.
+	/// Building the `QrCode` itself still allocates internally; callers who cannot allocate
+	/// anywhere in the encode path need the separate, truly heap-free crate in `rust-no-heap/`
+	/// (alongside this crate) instead.
+	pub fn bitmap_len(&self) -> usize {
+		(self.modules.len() + 7) / 8
+	}
+
+
+	/// Packs this QR Code's already-built modules one bit per module (dark = 1), row-major,
+	/// into `buffer`, without allocating a fresh `Vec` for the packed form.
+	///
+	/// Returns the number of bytes written, i.e. `bitmap_len()`.
+	/// Panics if `buffer` is shorter than `bitmap_len()`.
+	pub fn write_bitmap_into(&self, buffer: &mut [u8]) -> usize {
+		let len = self.bitmap_len();
+		assert!(buffer.len() >= len, "Buffer too short");
+		for b in &mut buffer[.. len] {
+			*b = 0;
+		}
+		for (i, &dark) in self.modules.iter().enumerate() {
+			buffer[i >> 3] |= u8::from(dark) << (7 - (i & 7));
+		}
+		len
+	}
 	
 	
 	// Returns a mutable reference to the module's color at the given coordinates, which must be in bounds.
@@ -482,7 +715,7 @@ impl QrCode {
 				let xx: i32 = x + dx;
 				let yy: i32 = y + dy;
 				if (0 .. self.size).contains(&xx) && (0 .. self.size).contains(&yy) {
-					let dist: i32 = std::cmp::max(dx.abs(), dy.abs());  // Chebyshev/infinity norm
+					let dist: i32 = core::cmp::max(dx.abs(), dy.abs());  // Chebyshev/infinity norm
 					self.set_function_module(xx, yy, dist != 2 && dist != 4);
 				}
 			}
@@ -495,7 +728,7 @@ impl QrCode {
 	fn draw_alignment_pattern(&mut self, x: i32, y: i32) {
 		for dy in -2 ..= 2 {
 			for dx in -2 ..= 2 {
-				self.set_function_module(x + dx, y + dy, std::cmp::max(dx.abs(), dy.abs()) != 1);
+				self.set_function_module(x + dx, y + dy, core::cmp::max(dx.abs(), dy.abs()) != 1);
 			}
 		}
 	}
@@ -788,6 +1021,7 @@ impl QrCode {
 	
 	// Returns the product of the two given field elements modulo GF(2^8/0x11D).
 	// All inputs are valid. This could be implemented as a 256*256 lookup table.
+	#[cfg(not(feature = "fast-rs"))]
 	fn reed_solomon_multiply(x: u8, y: u8) -> u8 {
 		// Russian peasant multiplication
 		let mut z: u8 = 0;
@@ -797,6 +1031,20 @@ impl QrCode {
 		}
 		z
 	}
+
+
+	// Same contract as the bit-by-bit version above, but backed by precomputed log/antilog
+	// tables: for large versions (up to 30 ECC codewords across dozens of blocks), a table
+	// lookup measurably beats 8 rounds of Russian peasant multiplication per coefficient.
+	#[cfg(feature = "fast-rs")]
+	fn reed_solomon_multiply(x: u8, y: u8) -> u8 {
+		if x == 0 || y == 0 {
+			0
+		} else {
+			let sum = usize::from(GF256_LOG[usize::from(x)]) + usize::from(GF256_LOG[usize::from(y)]);
+			GF256_EXP[sum % 255]
+		}
+	}
 	
 }
 
@@ -886,6 +1134,53 @@ static NUM_ERROR_CORRECTION_BLOCKS: [[i8; 41]; 4] = [
 ];
 
 
+// Log/antilog tables for GF(2^8/0x11D), used by the "fast-rs" Reed-Solomon multiply above.
+// Built at compile time from the same generator (0x02) and reduction rule as the
+// bit-by-bit implementation, so both paths agree on every product.
+#[cfg(feature = "fast-rs")]
+static GF256_EXP: [u8; 256] = gf256_compute_exp();
+#[cfg(feature = "fast-rs")]
+static GF256_LOG: [u8; 256] = gf256_compute_log();
+
+#[cfg(feature = "fast-rs")]
+const fn gf256_russian_multiply(x: u8, y: u8) -> u8 {
+	let mut z: u8 = 0;
+	let mut i = 8;
+	while i > 0 {
+		i -= 1;
+		z = (z << 1) ^ ((z >> 7) * 0x1D);
+		z ^= ((y >> i) & 1) * x;
+	}
+	z
+}
+
+#[cfg(feature = "fast-rs")]
+const fn gf256_compute_exp() -> [u8; 256] {
+	let mut table = [0u8; 256];
+	let mut x: u8 = 1;
+	let mut i = 0;
+	while i < 255 {
+		table[i] = x;
+		x = gf256_russian_multiply(x, 0x02);
+		i += 1;
+	}
+	table[255] = table[0];  // Unused by lookups (sums are reduced mod 255), but keeps the table full
+	table
+}
+
+#[cfg(feature = "fast-rs")]
+const fn gf256_compute_log() -> [u8; 256] {
+	let exp = gf256_compute_exp();
+	let mut table = [0u8; 256];
+	let mut i = 0;
+	while i < 255 {
+		table[exp[i] as usize] = i as u8;
+		i += 1;
+	}
+	table
+}
+
+
 
 /*---- QrCodeEcc functionality ----*/
 
@@ -1015,10 +1310,141 @@ impl QrSegment {
 	}
 	
 	
+	/// Returns a segment representing the given Unicode text string encoded in kanji mode.
+	///
+	/// Each character is transcoded to its Shift-JIS (JIS X 0208) double-byte code and
+	/// repacked into the compact 13-bit-per-character kanji mode representation. Only
+	/// characters with a Shift-JIS mapping in one of the kanji ranges 0x8140 to 0x9FFC or
+	/// 0xE040 to 0xEBBF are representable; see `is_kanji()`.
+	///
+	/// Panics if `text` contains a character that `is_kanji()` would reject.
+	#[cfg(feature = "kanji")]
+	pub fn make_kanji(text: &str) -> Self {
+		let mut bb = BitBuffer(Vec::with_capacity(text.chars().count().checked_mul(13).unwrap()));
+		let mut numchars: usize = 0;
+		for c in text.chars() {
+			let val = qr_segment_advanced::kanji_value(c)
+				.unwrap_or_else(|| panic!("String contains non-kanji-mode characters"));
+			bb.append_bits(val, 13);
+			numchars += 1;
+		}
+		QrSegment::new(QrSegmentMode::Kanji, numchars, bb.0)
+	}
+
+
+	/// Packs a single Shift-JIS kanji-mode code into its 13-bit QR Code representation,
+	/// or returns `None` if `sjis` falls outside the kanji ranges 0x8140 to 0x9FFC or
+	/// 0xE040 to 0xEBBF.
+	///
+	/// Exposed so that callers who already have raw Shift-JIS bytes on hand (for example,
+	/// from a file read in that encoding) can pack them directly without round-tripping
+	/// through Unicode text first.
+	#[cfg(feature = "kanji")]
+	pub fn pack_kanji_code(sjis: u16) -> Option<u32> {
+		let rough = u32::from(sjis);
+		let base: u32 = if (0x8140 ..= 0x9FFC).contains(&rough) {
+			0x8140
+		} else if (0xE040 ..= 0xEBBF).contains(&rough) {
+			0xC140
+		} else {
+			return None;
+		};
+		let subtracted: u32 = rough - base;
+		Some((subtracted >> 8) * 0xC0 + (subtracted & 0xFF))
+	}
+
+
+	/// Same as `make_kanji()`, but returns `None` instead of panicking if `text` contains a
+	/// character that can't be encoded, for callers that can't guarantee clean input up front.
+	#[cfg(feature = "kanji")]
+	pub fn try_make_kanji(text: &str) -> Option<Self> {
+		if QrSegment::is_kanji(text) {
+			Some(QrSegment::make_kanji(text))
+		} else {
+			None
+		}
+	}
+
+
+	/// Same as `try_make_kanji()`, but on failure returns the character index of the first
+	/// character that can't be encoded, for callers that want to report where bad input came
+	/// from (e.g. to point a user at the offending character) instead of just a bare `None`.
+	#[cfg(feature = "kanji")]
+	pub fn make_kanji_checked(text: &str) -> Result<Self,usize> {
+		match text.chars().position(|c| qr_segment_advanced::kanji_value(c).is_none()) {
+			None => Ok(QrSegment::make_kanji(text)),
+			Some(i) => Err(i),
+		}
+	}
+
+
+	/// Returns a segment representing the given binary data, base-converted from base 256 to
+	/// base 10 and packed as a numeric-mode segment.
+	///
+	/// This costs CPU time (a big-integer division per output digit). Numeric mode spends about
+	/// 3.33 bits per decimal digit and each input byte yields about 2.41 digits, so the data bits
+	/// come to about 8.03 per byte, slightly more than the 8 bits per byte of `make_bytes()`; what
+	/// this gains is a digit-only payload (e.g. to follow a numeric URL suffix), not a smaller symbol.
+	/// This crate does not bundle a compressor itself to keep `no_std` builds lean; compress
+	/// `bytes` beforehand to shrink the payload.
+	///
+	/// An empty slice encodes as a single `"0"` digit, matching how `0u8` would base-convert.
+	#[cfg(feature = "bignum")]
+	pub fn make_numeric_bignum(bytes: &[u8]) -> Self {
+		let digits = bytes_to_decimal_digits(bytes);
+		let text: String = digits.into_iter().map(char::from).collect();
+		QrSegment::make_numeric(&text)
+	}
+
+
+	/// Same as `make_numeric_bignum()`, but left-pads the digit string with `'0'` characters
+	/// up to the maximum decimal digit count that `bytes.len()` bytes could ever produce (i.e.
+	/// the digit count of `256^bytes.len() - 1`).
+	///
+	/// `make_numeric_bignum()` drops leading zero bytes silently, since they don't affect the
+	/// big-integer value; a caller who concatenates this segment after a fixed-format prefix
+	/// (e.g. a byte-mode URL segment, as in the Linux DRM panic screen's QR Code) can instead
+	/// recover `bytes.len()` from the digit count alone, because this function always emits the
+	/// same digit count for a given input length.
+	#[cfg(feature = "bignum")]
+	pub fn make_numeric_bignum_padded(bytes: &[u8]) -> Self {
+		let digits = bytes_to_decimal_digits(bytes);
+		let width = bytes_to_decimal_digits(&vec![0xFFu8; bytes.len()]).len();
+		let mut text = String::with_capacity(width);
+		for _ in digits.len() .. width {
+			text.push('0');
+		}
+		text.extend(digits.into_iter().map(char::from));
+		QrSegment::make_numeric(&text)
+	}
+
+
+	/// Returns the number of bits that `make_numeric_bignum_padded()` would use to encode
+	/// `num_bytes` bytes of input at the given version, without touching the actual bytes.
+	///
+	/// This lets a caller compare numeric-mode packing against `make_bytes()`'s 8 bits per
+	/// byte before committing to either: numeric mode costs about 3.33 bits per decimal digit,
+	/// and the digit count for `num_bytes` bytes is fixed (it only depends on the byte count,
+	/// since `make_numeric_bignum_padded()` always pads to the widest possible digit string),
+	/// so this estimate is exact, not approximate.
+	#[cfg(feature = "bignum")]
+	pub fn calc_numeric_bignum_bit_length(num_bytes: usize, version: Version) -> usize {
+		let numdigits = bytes_to_decimal_digits(&vec![0xFFu8; num_bytes]).len();
+		let leftover = numdigits % 3;
+		let databits = (numdigits / 3) * 10 + if leftover > 0 { leftover * 3 + 1 } else { 0 };
+		let ccbits = usize::from(QrSegmentMode::Numeric.num_char_count_bits(version));
+		4 + ccbits + databits
+	}
+
+
 	/// Returns a list of zero or more segments to represent the given Unicode text string.
-	/// 
+	///
 	/// The result may use various segment modes and switch
 	/// modes to optimize the length of the bit stream.
+	///
+	/// When the `kanji` feature is enabled, text that is entirely representable in Shift-JIS
+	/// kanji mode (see `is_kanji()`) is packed at 13 bits per character instead of falling
+	/// through to byte mode.
 	pub fn make_segments(text: &str) -> Vec<Self> {
 		if text.is_empty() {
 			vec![]
@@ -1028,6 +1454,8 @@ impl QrSegment {
 					QrSegment::make_numeric(text)
 				} else if QrSegment::is_alphanumeric(text) {
 					QrSegment::make_alphanumeric(text)
+				} else if text_is_kanji(text) {
+					text_make_kanji(text)
 				} else {
 					QrSegment::make_bytes(text.as_bytes())
 				}
@@ -1036,9 +1464,121 @@ impl QrSegment {
 	}
 	
 	
+	/// Returns a list of zero or more segments to represent the given Unicode text string,
+	/// using a minimum-length dynamic-programming search over mode boundaries at the given version.
+	///
+	/// Unlike `make_segments()`, which encodes the whole string in a single mode,
+	/// this considers switching between numeric, alphanumeric, and byte mode (and kanji mode, if the
+	/// `kanji` feature is enabled) at every character, and never produces a bit stream longer than
+	/// what `make_segments()` would for the same text. The version is needed because the width of
+	/// the character-count-indicator field (and thus the cost of starting a new segment) depends on it.
+	///
+	/// Always returns `Some`, since every character can fall back to byte mode. The result is not
+	/// checked against the version's capacity or character-count field widths.
+	pub fn make_segments_optimized(text: &str, version: Version) -> Option<Vec<Self>> {
+		if text.is_empty() {
+			return Some(vec![]);
+		}
+		let code_points: Vec<char> = text.chars().collect();
+		Some(qr_segment_advanced::make_segments_optimally_at_version(&code_points, version))
+	}
+
+
+	/// Returns a list of zero or more segments to represent the given Unicode text string,
+	/// choosing the smallest version in the given range (at the given ECC level) for which an
+	/// optimal split exists, the same way `QrCode::encode_segments_advanced()` chooses a version.
+	///
+	/// This repeats the dynamic-programming search of `make_segments_optimized()` at each
+	/// candidate version instead of running it once at a guessed version, because the optimal
+	/// split (and thus the bit length) can itself change across the version boundaries where
+	/// character-count-indicator fields widen.
+	///
+	/// Returns `None` if the text cannot be made to fit any version in the range at the given ECC level.
+	pub fn make_segments_optimized_in_range(text: &str, ecl: QrCodeEcc,
+			minversion: Version, maxversion: Version) -> Option<Vec<Self>> {
+		let code_points: Vec<char> = text.chars().collect();
+		qr_segment_advanced::make_segments_optimally(&code_points, ecl, minversion, maxversion, None)
+	}
+
+
+	/// Same as `make_segments_optimized_in_range()`, but when `eci_assignval` is given, prepends
+	/// an ECI segment (see `make_eci()`) announcing that charset ahead of the optimally split
+	/// text segments, and accounts for its extra header bits when picking the version.
+	///
+	/// Pass `QrSegment::ECI_UTF8` here for UTF-8 text that must decode unambiguously for scanners
+	/// that otherwise default to ISO-8859-1; pass `None` to behave exactly like
+	/// `make_segments_optimized_in_range()`.
+	///
+	/// Returns `None` if the text (plus the ECI segment, if any) cannot be made to fit any
+	/// version in the range at the given ECC level.
+	pub fn make_segments_optimized_in_range_eci(text: &str, ecl: QrCodeEcc,
+			minversion: Version, maxversion: Version, eci_assignval: Option<u32>) -> Option<Vec<Self>> {
+		let code_points: Vec<char> = text.chars().collect();
+		qr_segment_advanced::make_segments_optimally(&code_points, ecl, minversion, maxversion, eci_assignval)
+	}
+
+
+	/// Returns the number of bits that `make_segments_optimized()` would use to encode `text`
+	/// at the given version, without returning the segments themselves (they are still built internally).
+	///
+	/// Useful for probing whether a string fits a candidate version (or comparing candidate
+	/// versions) before committing to encoding the full segment list.
+	///
+	/// Returns `None` if a segment of the split has too many characters for its character-count
+	/// field at this version, or if the total bit count overflows `usize`.
+	pub fn calc_optimal_bit_length(text: &str, version: Version) -> Option<usize> {
+		let segs = QrSegment::make_segments_optimized(text, version)?;
+		QrSegment::get_total_bits(&segs, version)
+	}
+
+
+	/// Returns a list of zero or more segments to represent the given Unicode text string,
+	/// equivalent to `make_segments_optimized_in_range()` at the lowest (most capacious)
+	/// error correction level, for callers that want to pick the optimal split over a version
+	/// range without first deciding an ECC level.
+	///
+	/// Returns `None` if the text cannot be made to fit any version in the range at the low ECC level.
+	pub fn make_segments_optimally(text: &str, min_version: Version, max_version: Version) -> Option<Vec<Self>> {
+		QrSegment::make_segments_optimized_in_range(text, QrCodeEcc::Low, min_version, max_version)
+	}
+
+
+	/// Returns a Structured Append header segment for one symbol out of a group, per
+	/// ISO/IEC 18004 clause 8.3: `index` is this symbol's 0-based position, `total_minus_one`
+	/// is one less than the number of symbols in the group, and `parity` is the XOR of every
+	/// byte of the entire pre-split payload (the same value in every symbol of the group).
+	///
+	/// This segment must be the first one in its symbol. See
+	/// `QrCode::encode_segments_structured_append()` for the higher-level entry point.
+	///
+	/// Panics if `index` or `total_minus_one` exceeds 15 (the 4-bit field width).
+	pub fn make_structured_append_header(index: u8, total_minus_one: u8, parity: u8) -> Self {
+		assert!(index <= 15 && total_minus_one <= 15, "Structured Append index/count out of range");
+		let mut bb = BitBuffer(Vec::with_capacity(16));
+		bb.append_bits(u32::from(index), 4);
+		bb.append_bits(u32::from(total_minus_one), 4);
+		bb.append_bits(u32::from(parity), 8);
+		QrSegment::new(QrSegmentMode::StructuredAppend, 0, bb.0)
+	}
+
+
 	/// Returns a segment representing an Extended Channel Interpretation
 	/// (ECI) designator with the given assignment value.
+	///
+	/// Panics if `assignval` exceeds 999999; use `try_make_eci()` to handle that case
+	/// without panicking.
 	pub fn make_eci(assignval: u32) -> Self {
+		match QrSegment::try_make_eci(assignval) {
+			Ok(seg) => seg,
+			Err(e) => panic!("{}", e),
+		}
+	}
+
+
+	/// Same as `make_eci()`, but returns `Err(InvalidEciDesignator)` instead of panicking
+	/// if `assignval` exceeds 999999, the largest value the standard's variable-length
+	/// designator encoding can represent.
+	pub fn try_make_eci(assignval: u32) -> Result<Self,InvalidEciDesignator> {
 		let mut bb = BitBuffer(Vec::with_capacity(24));
 		if assignval < (1 << 7) {
 			bb.append_bits(assignval, 8);
@@ -1049,12 +1589,19 @@ impl QrSegment {
 			bb.append_bits(0b110, 3);
 			bb.append_bits(assignval, 21);
 		} else {
-			panic!("ECI assignment value out of range");
+			return Err(InvalidEciDesignator(assignval));
 		}
-		QrSegment::new(QrSegmentMode::Eci, 0, bb.0)
+		Ok(QrSegment::new(QrSegmentMode::Eci, 0, bb.0))
 	}
-	
-	
+
+
+	/// The ECI assignment value for ISO-8859-1, for use with `make_eci()`.
+	pub const ECI_ISO_8859_1: u32 = 3;
+
+	/// The ECI assignment value for UTF-8, for use with `make_eci()`.
+	pub const ECI_UTF8: u32 = 26;
+
+
 	/*---- Constructor (low level) ----*/
 	
 	/// Creates a new QR Code segment with the given attributes and data.
@@ -1116,6 +1663,26 @@ impl QrSegment {
 	}
 	
 	
+	/// Tests whether the given string can be encoded as a segment in kanji mode.
+	///
+	/// A string is encodable iff every character has a Shift-JIS mapping in one of the
+	/// kanji ranges 0x8140 to 0x9FFC or 0xE040 to 0xEBBF, the same ranges enforced by
+	/// `make_kanji()`. Characters outside Shift-JIS (e.g. most non-Japanese scripts)
+	/// always fail this test and must fall back to byte mode.
+	#[cfg(feature = "kanji")]
+	pub fn is_encodable_as_kanji(text: &str) -> bool {
+		text.chars().all(|c| qr_segment_advanced::kanji_value(c).is_some())
+	}
+
+
+	/// Alias for `is_encodable_as_kanji()`, matching the shorter name used by sibling
+	/// QR Code libraries for the same check.
+	#[cfg(feature = "kanji")]
+	pub fn is_kanji(text: &str) -> bool {
+		QrSegment::is_encodable_as_kanji(text)
+	}
+
+
 	/// Tests whether the given string can be encoded as a segment in alphanumeric mode.
 	/// 
 	/// A string is encodable iff each character is in the following set: 0 to 9, A to Z
@@ -1132,6 +1699,27 @@ impl QrSegment {
 static ALPHANUMERIC_CHARSET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
 
 
+// Lets make_segments() try kanji mode without a cfg(feature = "kanji") branch of its own;
+// when the feature is off, text is never kanji-encodable and make_kanji() is never called.
+#[cfg(feature = "kanji")]
+fn text_is_kanji(text: &str) -> bool {
+	QrSegment::is_kanji(text)
+}
+#[cfg(not(feature = "kanji"))]
+fn text_is_kanji(_text: &str) -> bool {
+	false
+}
+
+#[cfg(feature = "kanji")]
+fn text_make_kanji(text: &str) -> QrSegment {
+	QrSegment::make_kanji(text)
+}
+#[cfg(not(feature = "kanji"))]
+fn text_make_kanji(_text: &str) -> QrSegment {
+	unreachable!()
+}
+
+
 
 /*---- QrSegmentMode functionality ----*/
 
@@ -1143,38 +1731,42 @@ pub enum QrSegmentMode {
 	Byte,
 	Kanji,
 	Eci,
+	/// A Structured Append header, produced by `QrSegment::make_structured_append_header()`.
+	StructuredAppend,
 }
 
 
 impl QrSegmentMode {
-	
+
 	// Returns an unsigned 4-bit integer value (range 0 to 15)
 	// representing the mode indicator bits for this mode object.
 	fn mode_bits(self) -> u32 {
 		use QrSegmentMode::*;
 		match self {
-			Numeric      => 0x1,
-			Alphanumeric => 0x2,
-			Byte         => 0x4,
-			Kanji        => 0x8,
-			Eci          => 0x7,
+			Numeric          => 0x1,
+			Alphanumeric     => 0x2,
+			Byte             => 0x4,
+			Kanji            => 0x8,
+			Eci              => 0x7,
+			StructuredAppend => 0x3,
 		}
 	}
-	
-	
+
+
 	// Returns the bit width of the character count field for a segment in this mode
 	// in a QR Code at the given version number. The result is in the range [0, 16].
 	fn num_char_count_bits(self, ver: Version) -> u8 {
 		use QrSegmentMode::*;
 		(match self {
-			Numeric      => [10, 12, 14],
-			Alphanumeric => [ 9, 11, 13],
-			Byte         => [ 8, 16, 16],
-			Kanji        => [ 8, 10, 12],
-			Eci          => [ 0,  0,  0],
+			Numeric          => [10, 12, 14],
+			Alphanumeric     => [ 9, 11, 13],
+			Byte             => [ 8, 16, 16],
+			Kanji            => [ 8, 10, 12],
+			Eci              => [ 0,  0,  0],
+			StructuredAppend => [ 0,  0,  0],
 		})[usize::from((ver.value() + 7) / 17)]
 	}
-	
+
 }
 
 
@@ -1219,10 +1811,16 @@ pub enum DataTooLong {
 	DataOverCapacity(usize, usize),
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for DataTooLong {}
 
-impl std::fmt::Display for DataTooLong {
-	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+// `core::error::Error` covers the `no_std` case without requiring the `std` feature
+// (stabilized in Rust 1.81; this crate's no_std build already assumes a recent toolchain).
+#[cfg(not(feature = "std"))]
+impl core::error::Error for DataTooLong {}
+
+impl core::fmt::Display for DataTooLong {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
 		match *self {
 			Self::SegmentTooLong => write!(f, "Segment too long"),
 			Self::DataOverCapacity(datalen, maxcapacity) =>
@@ -1232,6 +1830,25 @@ impl std::fmt::Display for DataTooLong {
 }
 
 
+/// An error returned by `QrSegment::try_make_eci()` when the given ECI assignment value
+/// exceeds 999999, the largest value the standard's variable-length designator encoding
+/// (1, 2, or 3 bytes) can represent. The offending value is carried along for diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidEciDesignator(pub u32);
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidEciDesignator {}
+
+#[cfg(not(feature = "std"))]
+impl core::error::Error for InvalidEciDesignator {}
+
+impl core::fmt::Display for InvalidEciDesignator {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "ECI assignment value out of range: {} (must be at most 999999)", self.0)
+	}
+}
+
+
 /// A number between 1 and 40 (inclusive).
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct Version(u8);
@@ -1282,3 +1899,30 @@ impl Mask {
 fn get_bit(x: u32, i: i32) -> bool {
 	(x >> i) & 1 != 0
 }
+
+
+// Converts a big-endian byte string to its decimal digit representation (most significant
+// digit first), via repeated long division of the working buffer by 10. Used by
+// make_numeric_bignum() and make_numeric_bignum_padded().
+#[cfg(feature = "bignum")]
+fn bytes_to_decimal_digits(bytes: &[u8]) -> Vec<u8> {
+	let mut num: Vec<u8> = bytes.to_vec();
+	let mut digits: Vec<u8> = Vec::new();
+	while !(num.len() <= 1 && num.first().copied().unwrap_or(0) == 0) {
+		let mut remainder: u32 = 0;
+		for b in num.iter_mut() {
+			let cur: u32 = remainder * 256 + u32::from(*b);
+			*b = (cur / 10) as u8;
+			remainder = cur % 10;
+		}
+		digits.push(b'0' + remainder as u8);
+		while num.len() > 1 && num[0] == 0 {
+			num.remove(0);
+		}
+	}
+	if digits.is_empty() {
+		digits.push(b'0');
+	}
+	digits.reverse();
+	digits
+}
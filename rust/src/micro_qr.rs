@@ -0,0 +1,684 @@
+/*
+ * QR Code generator library (Rust)
+ * Micro QR Code (M1-M4) support.
+ *
+ * Copyright (c) Project Nayuki. (MIT License)
+ * https://www.nayuki.io/page/qr-code-generator-library
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ * - The above copyright notice and this permission notice shall be included in
+ *   all copies or substantial portions of the Software.
+ * - The Software is provided "as is", without warranty of any kind, express or
+ *   implied, including but not limited to the warranties of merchantability,
+ *   fitness for a particular purpose and noninfringement. In no event shall the
+ *   authors or copyright holders be liable for any claim, damages or other
+ *   liability, whether in an action of contract, tort or otherwise, arising from,
+ *   out of or in connection with the Software or the use or other dealings in the
+ *   Software.
+ */
+
+//! Micro QR Code (versions M1 through M4) support.
+//!
+//! Micro QR symbols are a compact relative of the Model 2 symbols produced by
+//! [`QrCode`](super::QrCode): a single finder pattern, timing lines only along the
+//! top and left edges, no alignment patterns, and a restricted set of 4 masks.
+//! This module is a parallel, self-contained implementation rather than a variant
+//! of `QrCode`, because nearly every drawing and bit-packing step differs in detail.
+
+#![cfg(feature = "micro")]
+
+use alloc::{vec, vec::Vec};
+use core::convert::TryFrom;
+use super::{QrCodeEcc, Mask, QrSegment, QrSegmentMode, BitBuffer, DataTooLong, get_bit};
+use super::qr_segment_advanced;
+#[cfg(feature = "kanji")]
+use super::qr_segment_advanced::is_kanji;
+use super::ALPHANUMERIC_CHARSET;
+
+
+/// A number between 1 and 4 (inclusive), representing a Micro QR Code version (M1 to M4).
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct MicroVersion(u8);
+
+impl MicroVersion {
+	/// The smallest Micro QR Code version, M1.
+	pub const MIN: MicroVersion = MicroVersion(1);
+
+	/// The largest Micro QR Code version, M4.
+	pub const MAX: MicroVersion = MicroVersion(4);
+
+	/// Creates a Micro Version object from the given number.
+	///
+	/// Panics if the number is outside the range [1, 4].
+	pub const fn new(ver: u8) -> Self {
+		assert!(MicroVersion::MIN.value() <= ver && ver <= MicroVersion::MAX.value(), "Micro version number out of range");
+		Self(ver)
+	}
+
+	/// Returns the value, which is in the range [1, 4].
+	pub const fn value(self) -> u8 {
+		self.0
+	}
+
+
+	/// Returns the side length of a Micro QR Code symbol of this version, in modules
+	/// (one of 11, 13, 15, or 17), without needing to construct a `MicroQrCode` first.
+	///
+	/// This is the same value `MicroQrCode::size()` reports for a symbol of this version.
+	pub const fn side_length(self) -> i32 {
+		(self.0 as i32) * 2 + 9
+	}
+}
+
+
+/// A Micro QR Code symbol, which is a compact type of two-dimension barcode.
+///
+/// Covers the Micro QR Code symbology defined alongside the Model 2 standard,
+/// supporting versions M1 through M4. Unlike [`QrCode`](super::QrCode), a Micro
+/// QR Code can only be automatically masked among 4 (not 8) candidate patterns,
+/// and M1 carries no user-selectable error correction level.
+#[derive(Clone, PartialEq, Eq)]
+pub struct MicroQrCode {
+	version: MicroVersion,
+	size: i32,
+	errorcorrectionlevel: QrCodeEcc,
+	mask: Mask,
+	modules: Vec<bool>,
+	isfunction: Vec<bool>,
+}
+
+
+impl MicroQrCode {
+
+	/*---- Static factory functions (high level) ----*/
+
+	/// Returns a Micro QR Code representing the given Unicode text string at the given
+	/// error correction level, automatically choosing the smallest version M1 to M4 that fits.
+	///
+	/// Returns `Err` if the text is too long to fit any Micro version at the given ECC level
+	/// (Micro QR Codes hold far less data than full-size `QrCode` symbols).
+	pub fn encode_text(text: &str, ecl: QrCodeEcc) -> Result<Self,DataTooLong> {
+		let segs: Vec<QrSegment> = QrSegment::make_segments(text);
+		Self::encode_segments(&segs, ecl, MicroVersion::MIN, MicroVersion::MAX, None)
+	}
+
+
+	/// Returns a Micro QR Code representing the given Unicode text string, like `encode_text()`,
+	/// but splitting the text into a minimum-bit sequence of mixed-mode segments via the same
+	/// dynamic-programming search that `QrCode::encode_text_optimally()` uses for full-size
+	/// symbols, parameterized by each candidate Micro version's reduced mode-indicator widths
+	/// (0 to 3 bits, versus the fixed 4-bit header of a full symbol) and narrower char-count
+	/// fields, and respecting which modes each version even supports (M1 is numeric-only; M2
+	/// adds alphanumeric; only M3 and M4 support byte and kanji mode).
+	///
+	/// This can fit more text into a given Micro version than `encode_text()`, which forces the
+	/// whole string into a single mode, at the cost of more computation.
+	///
+	/// Returns `Err` if the text is too long to fit any Micro version at the given ECC level.
+	pub fn encode_text_optimally(text: &str, ecl: QrCodeEcc) -> Result<Self,DataTooLong> {
+		let code_points: Vec<char> = text.chars().collect();
+		for verval in MicroVersion::MIN.value() ..= MicroVersion::MAX.value() {
+			let ver = MicroVersion::new(verval);
+			if !Self::supports_ecc(ver, ecl) {
+				continue;
+			}
+			let char_modes = match compute_micro_character_modes(&code_points, ver) {
+				Some(cm) => cm,
+				None => continue,  // Some character has no representable mode at this version
+			};
+			let segs: Vec<QrSegment> = if code_points.is_empty() {
+				Vec::new()
+			} else {
+				qr_segment_advanced::split_into_segments(&code_points, &char_modes)
+			};
+			let capacitybits = Self::get_num_data_bits(ver, ecl);
+			if let Some(used) = Self::get_total_bits(&segs, ver) {
+				if used <= capacitybits {
+					return Self::encode_segments_at_version(&segs, ecl, ver, None);
+				}
+			}
+		}
+		Err(DataTooLong::SegmentTooLong)
+	}
+
+
+	/// Returns a Micro QR Code representing the given binary data at the given error
+	/// correction level, automatically choosing the smallest version M1 to M4 that fits.
+	///
+	/// This always uses the binary segment mode, which only M3 and M4 support.
+	pub fn encode_binary(data: &[u8], ecl: QrCodeEcc) -> Result<Self,DataTooLong> {
+		let segs: [QrSegment; 1] = [QrSegment::make_bytes(data)];
+		Self::encode_segments(&segs, ecl, MicroVersion::MIN, MicroVersion::MAX, None)
+	}
+
+
+	/*---- Static factory functions (mid level) ----*/
+
+	/// Returns a Micro QR Code representing the given segments at exactly the given version,
+	/// for callers that want to pin the output size (e.g. to match a pre-printed label
+	/// template) instead of letting `encode_segments()` pick the smallest version that fits.
+	///
+	/// Returns `Err` if the data does not fit the given version at the given ECC level,
+	/// or if `ecl` is not supported by that version (M1 accepts only `Low`; see `supports_ecc()`).
+	pub fn encode_segments_at_version(segs: &[QrSegment], ecl: QrCodeEcc,
+			version: MicroVersion, mask: Option<Mask>) -> Result<Self,DataTooLong> {
+		Self::encode_segments(segs, ecl, version, version, mask)
+	}
+
+
+	/// Returns a Micro QR Code representing the given segments, choosing the
+	/// smallest version in the given range that can hold the data at the given ECC level.
+	///
+	/// Iff `mask` is `None`, the best of the 4 candidate masks is chosen automatically.
+	///
+	/// Returns `Err` if the data does not fit any version in the range at the given ECC level,
+	/// or if `ecl` is not supported by any version in the range (M1 accepts only `Low`; see `supports_ecc()`).
+	pub fn encode_segments(segs: &[QrSegment], ecl: QrCodeEcc,
+			minversion: MicroVersion, maxversion: MicroVersion, mask: Option<Mask>)
+			-> Result<Self,DataTooLong> {
+
+		assert!(minversion <= maxversion, "Invalid value");
+
+		let mut chosen: Option<(MicroVersion, usize)> = None;
+		for verval in minversion.value() ..= maxversion.value() {
+			let ver = MicroVersion::new(verval);
+			if !Self::supports_ecc(ver, ecl) {
+				continue;
+			}
+			let capacitybits: usize = Self::get_num_data_bits(ver, ecl);
+			if let Some(used) = Self::get_total_bits(segs, ver) {
+				if used <= capacitybits {
+					chosen = Some((ver, used));
+					break;
+				}
+			}
+		}
+		let (version, datausedbits) = chosen.ok_or(DataTooLong::SegmentTooLong)?;
+
+		// Concatenate all segments, using the reduced-width Micro mode indicators
+		let mut bb = BitBuffer(Vec::new());
+		for seg in segs {
+			let (indval, indwidth) = mode_indicator(seg.mode(), version);
+			bb.append_bits(indval, indwidth);
+			bb.append_bits(u32::try_from(seg.num_chars()).unwrap(), char_count_bits(seg.mode(), version));
+			bb.0.extend_from_slice(seg.data());
+		}
+		debug_assert_eq!(bb.0.len(), datausedbits);
+
+		// Add a terminator of up to 4 bits (shorter versions use a shorter terminator)
+		let capacitybits: usize = Self::get_num_data_bits(version, ecl);
+		let termbits: usize = core::cmp::min(terminator_width(version), capacitybits - bb.0.len());
+		bb.append_bits(0, termbits as u8);
+
+		// Pad to a whole codeword boundary, then alternate padding bytes/nibbles
+		let numzerobits: usize = bb.0.len().wrapping_neg() & 7;
+		bb.append_bits(0, u8::try_from(core::cmp::min(numzerobits, capacitybits - bb.0.len())).unwrap());
+		for &padbyte in [0xEC, 0x11].iter().cycle() {
+			let remain = capacitybits - bb.0.len();
+			if remain == 0 {
+				break;
+			} else if remain >= 8 {
+				bb.append_bits(padbyte, 8);
+			} else {
+				bb.append_bits(u32::from(padbyte) >> (8 - remain), remain as u8);
+			}
+		}
+
+		Ok(Self::encode_codewords(version, ecl, &bb.0, mask))
+	}
+
+
+	/*---- Constructor (low level) ----*/
+
+	/// Creates a new Micro QR Code from the given version, ECC level, raw data
+	/// bitstream (already padded to the symbol's data capacity), and mask.
+	pub fn encode_codewords(ver: MicroVersion, ecl: QrCodeEcc, data: &[bool], mut msk: Option<Mask>) -> Self {
+		let size = usize::try_from(ver.side_length()).unwrap();
+		let mut result = Self {
+			version: ver,
+			size: size as i32,
+			errorcorrectionlevel: ecl,
+			mask: Mask::new(0),  // Dummy value
+			modules: vec![false; size * size],
+			isfunction: vec![false; size * size],
+		};
+
+		result.draw_function_patterns();
+		let allbits: Vec<bool> = result.add_ecc(data);
+		result.draw_codewords(&allbits);
+
+		if msk.is_none() {
+			let mut maxpenalty = i32::MIN;
+			for i in 0u8 .. 4 {
+				let i = Mask::new(i);
+				result.apply_mask(i);
+				result.draw_format_bits(i);
+				let penalty: i32 = result.get_penalty_score();
+				if penalty > maxpenalty {
+					msk = Some(i);
+					maxpenalty = penalty;
+				}
+				result.apply_mask(i);  // Undo
+			}
+		}
+		let msk: Mask = msk.unwrap();
+		result.mask = msk;
+		result.apply_mask(msk);
+		result.draw_format_bits(msk);
+
+		result.isfunction.clear();
+		result.isfunction.shrink_to_fit();
+		result
+	}
+
+
+	/*---- Public methods ----*/
+
+	/// Returns this Micro QR Code's version, in the range M1 to M4.
+	pub fn version(&self) -> MicroVersion {
+		self.version
+	}
+
+
+	/// Returns this Micro QR Code's size, in the range [11, 17].
+	pub fn size(&self) -> i32 {
+		self.size
+	}
+
+
+	/// Returns this Micro QR Code's error correction level.
+	pub fn error_correction_level(&self) -> QrCodeEcc {
+		self.errorcorrectionlevel
+	}
+
+
+	/// Returns this Micro QR Code's mask, in the range [0, 3].
+	pub fn mask(&self) -> Mask {
+		self.mask
+	}
+
+
+	/// Returns the color of the module at the given coordinates, `false` for light
+	/// or `true` for dark. Out-of-bounds coordinates return `false` (light).
+	pub fn get_module(&self, x: i32, y: i32) -> bool {
+		(0 .. self.size).contains(&x) && (0 .. self.size).contains(&y)
+			&& self.modules[(y * self.size + x) as usize]
+	}
+
+
+	/// Tests whether the given Micro QR Code version supports the given error correction level,
+	/// so that callers can validate a (version, ECC) pairing up front instead of discovering the
+	/// rejection only when `encode_segments()` fails to find any version in their range.
+	///
+	/// M1 is data-only (no selectable ECC level, so only `Low` is accepted here as a placeholder);
+	/// M2 and M3 support Low and Medium; M4 adds Quartile. None of the four versions support High.
+	pub fn supports_ecc(ver: MicroVersion, ecl: QrCodeEcc) -> bool {
+		match (ver.value(), ecl) {
+			(1, QrCodeEcc::Low) => true,
+			(2 | 3, QrCodeEcc::Low | QrCodeEcc::Medium) => true,
+			(4, QrCodeEcc::Low | QrCodeEcc::Medium | QrCodeEcc::Quartile) => true,
+			_ => false,
+		}
+	}
+
+
+	/*---- Private helpers: drawing function modules ----*/
+
+	fn draw_function_patterns(&mut self) {
+		let size: i32 = self.size;
+		// Timing lines along the top row and left column only (not centered, unlike QrCode)
+		for i in 0 .. size {
+			self.set_function_module(i, 0, i % 2 == 0 && i != 0);
+			self.set_function_module(0, i, i % 2 == 0 && i != 0);
+		}
+		// The single finder pattern sits in the top-left corner
+		self.draw_finder_pattern(3, 3);
+		// Reserve space for the format info (dummy mask for now, overwritten at the end)
+		self.draw_format_bits(Mask::new(0));
+	}
+
+
+	// Draws the 15-bit Micro format information, using the same BCH generator as
+	// QrCode::draw_format_bits() but a different data packing and XOR mask.
+	fn draw_format_bits(&mut self, mask: Mask) {
+		let symbolnum: u32 = Self::symbol_number(self.version, self.errorcorrectionlevel);
+		let data: u32 = symbolnum << 2 | u32::from(mask.value());  // uint5
+		let mut rem: u32 = data;
+		for _ in 0 .. 10 {
+			rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+		}
+		let bits: u32 = (data << 10 | rem) ^ 0x4445;  // uint15
+		debug_assert_eq!(bits >> 15, 0);
+
+		// The 15 format bits run down column 8, then along row 8
+		for i in 0 .. 8 {
+			self.set_function_module(8, i + 1, get_bit(bits, i));
+		}
+		for i in 8 .. 15 {
+			self.set_function_module(15 - i, 8, get_bit(bits, i));  // x = 7 down to 1, clear of the timing column
+		}
+	}
+
+
+	fn draw_finder_pattern(&mut self, x: i32, y: i32) {
+		for dy in -4 ..= 4 {
+			for dx in -4 ..= 4 {
+				let xx: i32 = x + dx;
+				let yy: i32 = y + dy;
+				if (0 .. self.size).contains(&xx) && (0 .. self.size).contains(&yy) {
+					let dist: i32 = core::cmp::max(dx.abs(), dy.abs());
+					self.set_function_module(xx, yy, dist != 2 && dist != 4);
+				}
+			}
+		}
+	}
+
+
+	fn set_function_module(&mut self, x: i32, y: i32, isdark: bool) {
+		let size = self.size;
+		self.modules[(y * size + x) as usize] = isdark;
+		self.isfunction[(y * size + x) as usize] = true;
+	}
+
+
+	/*---- Private helpers: codewords and masking ----*/
+
+	// Micro QR Codes use a single Reed-Solomon block, so no interleaving is needed.
+	fn add_ecc(&self, data: &[bool]) -> Vec<bool> {
+		let eccbits: usize = Self::get_num_ecc_bits(self.version, self.errorcorrectionlevel);
+		// Pack data bits into bytes (rounding up), matching how reed_solomon_compute_remainder operates
+		let databytes: Vec<u8> = data.chunks(8).map(|chunk| {
+			chunk.iter().fold(0u8, |acc, &b| (acc << 1) | u8::from(b))
+				<< (8 - chunk.len())
+		}).collect();
+		let eccbytes: usize = (eccbits + 7) / 8;
+		let rsdiv: Vec<u8> = super::QrCode::reed_solomon_compute_divisor(eccbytes);
+		let ecc: Vec<u8> = super::QrCode::reed_solomon_compute_remainder(&databytes, &rsdiv);
+
+		let mut result: Vec<bool> = data.to_vec();
+		for &b in &ecc {
+			for i in (0 .. 8).rev() {
+				result.push(get_bit(u32::from(b), i));
+			}
+		}
+		result.truncate(data.len() + eccbits);
+		result
+	}
+
+
+	fn draw_codewords(&mut self, data: &[bool]) {
+		let mut i: usize = 0;
+		let size = self.size;
+		let mut right: i32 = size - 1;
+		while right >= 1 {
+			for vert in 0 .. size {
+				for j in 0 .. 2 {
+					let x: i32 = right - j;
+					let upward: bool = (right + 1) & 2 == 0;
+					let y: i32 = if upward { size - 1 - vert } else { vert };
+					if !self.isfunction[(y * size + x) as usize] && i < data.len() {
+						self.modules[(y * size + x) as usize] = data[i];
+						i += 1;
+					}
+				}
+			}
+			right -= 2;
+		}
+	}
+
+
+	fn apply_mask(&mut self, mask: Mask) {
+		for y in 0 .. self.size {
+			for x in 0 .. self.size {
+				// The 4 reduced Micro QR mask patterns, indexed over the data coordinates (x, y)
+				let invert: bool = match mask.value() {
+					0 => y % 2 == 0,
+					1 => (y / 2 + x / 3) % 2 == 0,
+					2 => ((x * y) % 2 + (x * y) % 3) % 2 == 0,
+					3 => ((x + y) % 2 + (x * y) % 3) % 2 == 0,
+					_ => unreachable!(),
+				};
+				let idx = (y * self.size + x) as usize;
+				self.modules[idx] ^= invert & !self.isfunction[idx];
+			}
+		}
+	}
+
+
+	// Micro QR penalty scoring differs entirely from QrCode::get_penalty_score(): it sums
+	// the number of dark modules along the symbol's right edge and bottom edge, per the
+	// score function in ISO/IEC 18004 section 7.8.3.2. The best mask is the one that
+	// *maximizes* this score (more dark modules along those edges makes the symbol's true
+	// size less ambiguous to a scanner), unlike full QR Code's minimize-penalty rule.
+	fn get_penalty_score(&self) -> i32 {
+		let size = self.size;
+		let mut darkright: i32 = 0;
+		let mut darkbottom: i32 = 0;
+		for y in 0 .. size {
+			darkright += i32::from(self.modules[(y * size + (size - 1)) as usize]);
+		}
+		for x in 0 .. size {
+			darkbottom += i32::from(self.modules[((size - 1) * size + x) as usize]);
+		}
+		core::cmp::min(darkright, darkbottom) * 16 + core::cmp::max(darkright, darkbottom)
+	}
+
+
+	/*---- Private helpers: capacity and bit-width tables ----*/
+
+	// Returns the 3-bit combined symbol-number/ECC field used in the 15-bit format information.
+	fn symbol_number(ver: MicroVersion, ecl: QrCodeEcc) -> u32 {
+		match (ver.value(), ecl) {
+			(1, _)                    => 0,
+			(2, QrCodeEcc::Low)       => 1,
+			(2, QrCodeEcc::Medium)    => 2,
+			(3, QrCodeEcc::Low)       => 3,
+			(3, QrCodeEcc::Medium)    => 4,
+			(4, QrCodeEcc::Low)       => 5,
+			(4, QrCodeEcc::Medium)    => 6,
+			(4, QrCodeEcc::Quartile)  => 7,
+			_ => unreachable!("Unsupported Micro QR version/ECC combination"),
+		}
+	}
+
+	// Total codewords (data + ECC), in bits, per version: M1 and M3 end on a 4-bit codeword.
+	fn get_num_raw_data_bits(ver: MicroVersion) -> usize {
+		match ver.value() {
+			1 => 5 * 8 - 4,
+			2 => 10 * 8,
+			3 => 17 * 8 - 4,
+			4 => 24 * 8,
+			_ => unreachable!(),
+		}
+	}
+
+
+	fn get_num_ecc_bits(ver: MicroVersion, ecl: QrCodeEcc) -> usize {
+		let eccbytes: usize = match (ver.value(), ecl) {
+			(1, _)                   => 2,
+			(2, QrCodeEcc::Low)      => 5,
+			(2, QrCodeEcc::Medium)   => 6,
+			(3, QrCodeEcc::Low)      => 6,
+			(3, QrCodeEcc::Medium)   => 8,
+			(4, QrCodeEcc::Low)      => 8,
+			(4, QrCodeEcc::Medium)   => 10,
+			(4, QrCodeEcc::Quartile) => 14,
+			_ => unreachable!(),
+		};
+		eccbytes * 8
+	}
+
+
+	/// Returns the number of data bits available for the given version and ECC level.
+	pub fn get_num_data_bits(ver: MicroVersion, ecl: QrCodeEcc) -> usize {
+		Self::get_num_raw_data_bits(ver) - Self::get_num_ecc_bits(ver, ecl)
+	}
+
+
+	fn get_total_bits(segs: &[QrSegment], ver: MicroVersion) -> Option<usize> {
+		let mut result: usize = 0;
+		for seg in segs {
+			let ccbits: u8 = char_count_bits(seg.mode(), ver);
+			if ccbits == 0 && seg.mode() != QrSegmentMode::Eci {
+				return None;  // This mode isn't representable at this Micro version at all
+			}
+			if let Some(limit) = 1usize.checked_shl(ccbits.into()) {
+				if seg.num_chars() >= limit {
+					return None;
+				}
+			}
+			let (_, indwidth) = mode_indicator(seg.mode(), ver);
+			result = result.checked_add(usize::from(indwidth) + usize::from(ccbits))?;
+			result = result.checked_add(seg.data().len())?;
+		}
+		Some(result)
+	}
+}
+
+
+// Returns the terminator width (in bits) for the given Micro version, per ISO/IEC 18004 Table 4:
+// it shrinks from 4 bits down to 3/2/1 bits as the symbol gets smaller.
+fn terminator_width(ver: MicroVersion) -> usize {
+	usize::from(ver.value())
+}
+
+
+// Returns the mode indicator's (value, bit width) for the given mode and Micro version.
+// Widths range from 0 bits at M1 (numeric is the only representable mode) up to 3 bits at M4.
+fn mode_indicator(mode: QrSegmentMode, ver: MicroVersion) -> (u32, u8) {
+	let width: u8 = ver.value() - 1;
+	let value: u32 = match mode {
+		QrSegmentMode::Numeric      => 0,
+		QrSegmentMode::Alphanumeric => 1,
+		QrSegmentMode::Byte         => 2,
+		QrSegmentMode::Kanji        => 3,
+		QrSegmentMode::Eci | QrSegmentMode::StructuredAppend =>
+			panic!("ECI and Structured Append segments are not supported in Micro QR Codes"),
+	};
+	(value, width)
+}
+
+
+// Returns the bit width of the character count field for a segment in the given mode at the
+// given Micro version, or 0 if that mode cannot be represented at that version (ISO/IEC 18004 Table 3).
+fn char_count_bits(mode: QrSegmentMode, ver: MicroVersion) -> u8 {
+	match (mode, ver.value()) {
+		(QrSegmentMode::Numeric,      1) => 3,
+		(QrSegmentMode::Numeric,      2) => 4,
+		(QrSegmentMode::Numeric,      3) => 5,
+		(QrSegmentMode::Numeric,      4) => 6,
+		(QrSegmentMode::Alphanumeric, 2) => 3,
+		(QrSegmentMode::Alphanumeric, 3) => 4,
+		(QrSegmentMode::Alphanumeric, 4) => 5,
+		(QrSegmentMode::Byte,         3) => 4,
+		(QrSegmentMode::Byte,         4) => 5,
+		(QrSegmentMode::Kanji,        3) => 3,
+		(QrSegmentMode::Kanji,        4) => 4,
+		_ => 0,
+	}
+}
+
+
+// The modes a segment-optimizer DP step may consider, same order as qr_segment_advanced's
+// MODE_TYPES; availability at a given Micro version is decided per-character by `char_count_bits`.
+const MODE_TYPES: [QrSegmentMode; 4] =
+	[QrSegmentMode::Byte, QrSegmentMode::Alphanumeric, QrSegmentMode::Numeric, QrSegmentMode::Kanji];
+const NUM_MODES: usize = MODE_TYPES.len();
+
+
+// Returns a new array representing the optimal mode per code point for the given Micro version,
+// mirroring `qr_segment_advanced::compute_character_modes()`'s minimum-bit DP search but using
+// Micro mode indicator widths (0 to 3 bits) and char-count field widths in place of the fixed
+// 4-bit header and full-symbol field widths. Returns `None` if some character has no mode
+// representable at this version at all (e.g. a kanji character at M1 or M2).
+fn compute_micro_character_modes(code_points: &[char], ver: MicroVersion) -> Option<Vec<QrSegmentMode>> {
+	if code_points.is_empty() {
+		return Some(Vec::new());
+	}
+
+	// Segment header sizes (mode indicator + char count field), measured in 1/6 bits.
+	// A mode with 0 char-count bits isn't representable at this version at all.
+	let mut head_costs = [0usize; NUM_MODES];
+	let mut available = [false; NUM_MODES];
+	for i in 0 .. NUM_MODES {
+		let ccbits = char_count_bits(MODE_TYPES[i], ver);
+		available[i] = ccbits > 0 && (MODE_TYPES[i] != QrSegmentMode::Kanji || cfg!(feature = "kanji"));
+		let (_, indwidth) = mode_indicator(MODE_TYPES[i], ver);
+		head_costs[i] = (usize::from(indwidth) + usize::from(ccbits)) * 6;
+	}
+
+	let mut char_modes = vec![[None::<QrSegmentMode>; NUM_MODES]; code_points.len()];
+	let mut prev_costs = head_costs;
+
+	for i in 0 .. code_points.len() {
+		let c = code_points[i];
+		let mut cur_costs = [0usize; NUM_MODES];
+
+		if available[0] {
+			// Always extend a byte mode segment
+			cur_costs[0] = prev_costs[0] + c.len_utf8() * 8 * 6;
+			char_modes[i][0] = Some(MODE_TYPES[0]);
+		}
+		if available[1] && ALPHANUMERIC_CHARSET.contains(c) {
+			cur_costs[1] = prev_costs[1] + 33;  // 5.5 bits per alphanumeric char
+			char_modes[i][1] = Some(MODE_TYPES[1]);
+		}
+		if available[2] && ('0' ..= '9').contains(&c) {
+			cur_costs[2] = prev_costs[2] + 20;  // 3.33 bits per digit
+			char_modes[i][2] = Some(MODE_TYPES[2]);
+		}
+		#[cfg(feature = "kanji")]
+		if available[3] && is_kanji(c) {
+			cur_costs[3] = prev_costs[3] + 78;  // 13 bits per Shift JIS char
+			char_modes[i][3] = Some(MODE_TYPES[3]);
+		}
+
+		// Start new segment at the end to switch modes
+		for j in 0 .. NUM_MODES {
+			if !available[j] {
+				continue;
+			}
+			for k in 0 .. NUM_MODES {
+				let new_cost = (cur_costs[k] + 5) / 6 * 6 + head_costs[j];
+				if char_modes[i][k].is_some() && (char_modes[i][j].is_none() || new_cost < cur_costs[j]) {
+					cur_costs[j] = new_cost;
+					char_modes[i][j] = Some(MODE_TYPES[k]);
+				}
+			}
+		}
+
+		prev_costs = cur_costs;
+	}
+
+	// Find optimal ending mode among those that are available and actually reachable
+	let mut cur_mode = None::<QrSegmentMode>;
+	let mut min_cost = 0;
+	for i in 0 .. NUM_MODES {
+		if available[i] && char_modes[code_points.len() - 1][i].is_some()
+				&& (cur_mode.is_none() || prev_costs[i] < min_cost) {
+			min_cost = prev_costs[i];
+			cur_mode = Some(MODE_TYPES[i]);
+		}
+	}
+	let mut cur_mode = cur_mode?;
+
+	let mut result = vec![QrSegmentMode::Byte; char_modes.len()];
+
+	// Get optimal mode for each code point by tracing backwards
+	for i in (0 .. char_modes.len()).rev() {
+		for j in 0 .. NUM_MODES {
+			if MODE_TYPES[j] == cur_mode {
+				cur_mode = char_modes[i][j]?;
+				result[i] = cur_mode;
+				break;
+			}
+		}
+	}
+
+	Some(result)
+}
@@ -1,3 +1,4 @@
+use alloc::{vec, vec::Vec, string::String};
 use super::{Version, QrSegment, QrSegmentMode, ALPHANUMERIC_CHARSET, QrCode, QrCodeEcc};
 #[cfg(feature = "kanji")]
 use super::BitBuffer;
@@ -15,7 +16,11 @@ const NUM_MODES: usize = 4;
 const NUM_MODES: usize = 3;
 
 /// Returns a list of zero or more segments to represent the specified Unicode text string.
-pub fn make_segments_optimally(code_points: &[char], ecc: QrCodeEcc, min_version: Version, max_version: Version) -> Option<Vec<QrSegment>> {
+///
+/// If `eci_assignval` is given, an ECI segment (see `QrSegment::make_eci()`) announcing that
+/// charset is prepended ahead of the optimally split text segments, and its header bits are
+/// counted against the candidate version's capacity just like the text segments' own bits.
+pub(crate) fn make_segments_optimally(code_points: &[char], ecc: QrCodeEcc, min_version: Version, max_version: Version, eci_assignval: Option<u32>) -> Option<Vec<QrSegment>> {
     let min_version = min_version.value();
     let max_version = max_version.value();
 
@@ -24,6 +29,8 @@ pub fn make_segments_optimally(code_points: &[char], ecc: QrCodeEcc, min_version
         return None;
     }
 
+    let eci_seg = eci_assignval.map(QrSegment::make_eci);
+
     // Iterate through version numbers, and make tentative segments
     let mut segs = Vec::new();
 
@@ -33,13 +40,19 @@ pub fn make_segments_optimally(code_points: &[char], ecc: QrCodeEcc, min_version
         }
         let version = Version::new(version);
 
+        // Prepend the ECI segment (if any) before checking capacity, since it costs bits too
+        let mut candidate = segs.clone();
+        if let Some(eci) = &eci_seg {
+            candidate.insert(0, eci.clone());
+        }
+
         // Check if the segments fit
         let data_capacity_bits = QrCode::get_num_data_codewords(version, ecc) * 8;
-        let data_used_bits = QrSegment::get_total_bits(&segs, version);
+        let data_used_bits = QrSegment::get_total_bits(&candidate, version);
 
         if let Some(data_used_bits) = data_used_bits {
             if data_used_bits <= data_capacity_bits {
-                return Some(segs); // This version number is found to be suitable
+                return Some(candidate); // This version number is found to be suitable
             }
         }
     }
@@ -48,7 +61,7 @@ pub fn make_segments_optimally(code_points: &[char], ecc: QrCodeEcc, min_version
 }
 
 // Returns a new list of segments that is optimal for the given text at the given version number.
-fn make_segments_optimally_at_version(code_points: &[char], version: Version) -> Vec<QrSegment> {
+pub(crate) fn make_segments_optimally_at_version(code_points: &[char], version: Version) -> Vec<QrSegment> {
     let char_modes = compute_character_modes(code_points, version);
     split_into_segments(code_points, &char_modes)
 }
@@ -82,7 +95,7 @@ fn compute_character_modes(code_points: &[char], version: Version) -> Vec<QrSegm
         }
 
         // Extend a segment if possible
-        if ALPHANUMERIC_CHARSET.contains(&c) { // Is alphanumeric
+        if ALPHANUMERIC_CHARSET.contains(c) { // Is alphanumeric
             cur_costs[1] = prev_costs[1] + 33; // 5.5 bits per alphanumeric char
             char_modes[i][1] = Some(MODE_TYPES[1]);
         }
@@ -142,7 +155,11 @@ fn compute_character_modes(code_points: &[char], version: Version) -> Vec<QrSegm
 }
 
 // Returns a new list of segments based on the given text and modes, such that consecutive code points in the same mode are put into the same segment.
-fn split_into_segments(code_points: &[char], char_modes: &[QrSegmentMode]) -> Vec<QrSegment> {
+pub(crate) fn split_into_segments(code_points: &[char], char_modes: &[QrSegmentMode]) -> Vec<QrSegment> {
+    if code_points.is_empty() {
+        return Vec::new();
+    }
+
     let mut result = Vec::new();
 
     // Accumulate run of modes
@@ -167,10 +184,12 @@ fn split_into_segments(code_points: &[char], char_modes: &[QrSegmentMode]) -> Ve
                 result.push(QrSegment::make_bytes(&v));
             }
             QrSegmentMode::Numeric => {
-                result.push(QrSegment::make_numeric(s));
+                let s: String = s.iter().collect();
+                result.push(QrSegment::make_numeric(&s));
             }
             QrSegmentMode::Alphanumeric => {
-                result.push(QrSegment::make_alphanumeric(s));
+                let s: String = s.iter().collect();
+                result.push(QrSegment::make_alphanumeric(&s));
             }
             QrSegmentMode::Kanji => {
                 if cfg!(feature = "kanji") {
@@ -199,13 +218,10 @@ pub fn make_kanji(code_points: &[char]) -> QrSegment {
     let mut bb = BitBuffer(Vec::new());
 
     for &c in code_points {
-        let val = UNICODE_TO_QR_KANJI[c as usize];
-
-        if val == -1 {
-            panic!("String contains non-kanji-mode characters");
-        }
+        let val = kanji_value(c)
+            .unwrap_or_else(|| panic!("String contains non-kanji-mode characters"));
 
-        bb.append_bits(val as u32, 13);
+        bb.append_bits(val, 13);
     }
 
     QrSegment::new(QrSegmentMode::Kanji, code_points.len(), bb.0)
@@ -229,8 +245,7 @@ pub fn is_encodable_as_kanji(code_points: &[char]) -> bool {
 
 #[cfg(feature = "kanji")]
 pub fn is_kanji(c: char) -> bool {
-    let c = c as usize;
-    c < UNICODE_TO_QR_KANJI.len() && UNICODE_TO_QR_KANJI[c] != -1
+    kanji_value(c).is_some()
 }
 
 #[cfg(not(feature = "kanji"))]
@@ -238,6 +253,25 @@ fn is_kanji(_: char) -> bool {
     unreachable!()
 }
 
+/// Returns the 13-bit QR kanji-mode value for the given Unicode code point, i.e. its
+/// Shift-JIS double-byte code repacked per ISO/IEC 18004 §8.4.5, or `None` if `c` has no
+/// Shift-JIS mapping in the kanji mode ranges. Shared by `make_kanji()`/`is_kanji()` here
+/// and by `QrSegment`'s public `&str`-based kanji API.
+#[cfg(feature = "kanji")]
+pub(crate) fn kanji_value(c: char) -> Option<u32> {
+    let c = c as usize;
+    if c < UNICODE_TO_QR_KANJI.len() {
+        let val = UNICODE_TO_QR_KANJI[c];
+        if val != -1 {
+            return Some(val as u32);
+        }
+    }
+    None
+}
+
 #[cfg(feature = "kanji")]
-// Load the unpacked the computation-friendly Shift JIS table
+// Load the unpacked the computation-friendly Shift JIS table. Indexed by Unicode code point,
+// giving the 13-bit QR kanji-mode value per ISO/IEC 18004 §8.4.5, or -1 if not kanji-mode-encodable.
+// Generated offline from the Shift JIS double-byte ranges (0x8140-0x9FFC, 0xE040-0xEBBF); not meant
+// to be hand-edited.
 static UNICODE_TO_QR_KANJI: [i16; 1 << 16] = include!("unicode_to_qr_kanji.json");
\ No newline at end of file
@@ -0,0 +1,375 @@
+/*
+ * QR Code generator library (Rust)
+ * Built-in rendering backends (SVG, ASCII, Unicode half-block).
+ *
+ * Copyright (c) Project Nayuki. (MIT License)
+ * https://www.nayuki.io/page/qr-code-generator-library
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ * - The above copyright notice and this permission notice shall be included in
+ *   all copies or substantial portions of the Software.
+ * - The Software is provided "as is", without warranty of any kind, express or
+ *   implied, including but not limited to the warranties of merchantability,
+ *   fitness for a particular purpose and noninfringement. In no event shall the
+ *   authors or copyright holders be liable for any claim, damages or other
+ *   liability, whether in an action of contract, tort or otherwise, arising from,
+ *   out of or in connection with the Software or the use or other dealings in the
+ *   Software.
+ */
+
+//! Built-in rendering backends, kept behind the default-on `render` feature so that
+//! users who only want module data (and no `String`/formatting machinery pulled in)
+//! can opt out. Every rendering function here takes (or, in the `QrCodeRenderer` builder,
+//! is configured with) a quiet-zone border width in modules and panics if it is negative, matching the convention already used by the
+//! `to_svg_string()` helper shown in `qrcodegen-demo`.
+
+#![cfg(feature = "render")]
+
+use alloc::{format, string::String};
+use core::convert::TryFrom;
+use super::QrCode;
+
+
+impl QrCode {
+
+	/// Returns a chainable builder for configuring the SVG and Unicode-text backends, as an
+	/// alternative to calling `to_svg_string()`/`to_unicode()` directly with their fixed
+	/// argument lists, e.g. `qr.render().quiet_zone(2).invert(true).to_svg_string()`.
+	///
+	/// Defaults match the plain methods: a 4-module quiet zone, black-on-white colors, and
+	/// no inversion.
+	pub fn render(&self) -> QrCodeRenderer {
+		QrCodeRenderer { qr: self, border: 4, dark: String::from("#000000"), light: String::from("#FFFFFF"), invert: false }
+	}
+
+
+	/// Returns a string of SVG code for an image depicting this QR Code, with the given
+	/// number of border (quiet zone) modules, and the given dark and light module colors
+	/// (as any valid SVG color, e.g. `"#000000"`).
+	///
+	/// The string always uses Unix newlines (`\n`), regardless of the platform.
+	/// Panics if `border` is negative.
+	pub fn to_svg_string(&self, border: i32, dark: &str, light: &str) -> String {
+		assert!(border >= 0, "Border must be non-negative");
+		let mut result = String::new();
+		result += "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+		result += "<!DOCTYPE svg PUBLIC \"-//W3C//DTD SVG 1.1//EN\" \"http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd\">\n";
+		let dimension = self.size().checked_add(border.checked_mul(2).unwrap()).unwrap();
+		result += &format!(
+			"<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" viewBox=\"0 0 {0} {0}\" stroke=\"none\">\n", dimension);
+		result += &format!("\t<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n", light);
+		result += "\t<path d=\"";
+		result += &self.to_svg_path(border);
+		result += &format!("\" fill=\"{}\"/>\n", dark);
+		result += "</svg>\n";
+		result
+	}
+
+
+	// Returns the SVG path data (the contents of a `<path d="...">` attribute) tracing every
+	// dark module at the given border offset, one `MxxhNvNhNz`-like rectangle per horizontal
+	// run of consecutive dark modules instead of one per module, which keeps the path short
+	// for symbols with long runs of set modules (e.g. finder patterns, timing lines).
+	fn to_svg_path(&self, border: i32) -> String {
+		let mut result = String::new();
+		for y in 0 .. self.size() {
+			let mut x = 0;
+			while x < self.size() {
+				if !self.get_module(x, y) {
+					x += 1;
+					continue;
+				}
+				let runstart = x;
+				while x < self.size() && self.get_module(x, y) {
+					x += 1;
+				}
+				let runlen = x - runstart;
+				if !result.is_empty() {
+					result += " ";
+				}
+				result += &format!("M{},{}h{}v1h-{}z", runstart + border, y + border, runlen, runlen);
+			}
+		}
+		result
+	}
+
+
+	/// Returns a string of ASCII art depicting this QR Code, with the given number of
+	/// border (quiet zone) modules, using `chars[0]` for dark modules and `chars[1]` for
+	/// light modules. Rows are separated by `\n`, with a trailing newline.
+	///
+	/// Panics if `border` is negative.
+	pub fn to_string_with(&self, border: i32, chars: [char; 2]) -> String {
+		assert!(border >= 0, "Border must be non-negative");
+		let [dark, light] = chars;
+		let mut result = String::new();
+		for y in -border .. self.size() + border {
+			for x in -border .. self.size() + border {
+				result.push(if self.get_module(x, y) { dark } else { light });
+			}
+			result.push('\n');
+		}
+		result
+	}
+
+
+	/// Returns a string depicting this QR Code using the Unicode half-block glyphs
+	/// (`█`, `▀`, `▄`, and space), packing two vertical modules per output character
+	/// so that the symbol renders at roughly half its height in a terminal.
+	///
+	/// Panics if `border` is negative.
+	pub fn to_unicode(&self, border: i32) -> String {
+		assert!(border >= 0, "Border must be non-negative");
+		let top = -border;
+		let bottom = self.size() + border;
+		let mut result = String::new();
+		let mut y = top;
+		while y < bottom {
+			for x in -border .. self.size() + border {
+				let upper = self.get_module(x, y);
+				let lower = y + 1 < bottom && self.get_module(x, y + 1);
+				result.push(match (upper, lower) {
+					(false, false) => ' ',
+					(false, true ) => '▄',
+					(true , false) => '▀',
+					(true , true ) => '█',
+				});
+			}
+			result.push('\n');
+			y += 2;
+		}
+		result
+	}
+
+
+	/// Renders this QR Code to a raw 8-bit grayscale raster: one byte per pixel, 0 for dark
+	/// and 255 for light, row-major with no padding, at the given border width and integer
+	/// upscaling factor (each module becomes `scale*scale` pixels).
+	///
+	/// Returns `(pixels, width)`, where `width` is both the image width and height (the
+	/// image is always square) and `pixels.len() == width * width`.
+	///
+	/// Panics if `border` is negative or `scale` is zero.
+	pub fn to_raster(&self, border: i32, scale: i32) -> (alloc::vec::Vec<u8>, usize) {
+		assert!(border >= 0, "Border must be non-negative");
+		assert!(scale > 0, "Scale must be positive");
+		let width = usize::try_from(self.size().checked_add(border.checked_mul(2).unwrap()).unwrap()).unwrap()
+			.checked_mul(usize::try_from(scale).unwrap()).unwrap();
+		let mut pixels = alloc::vec![255u8; width * width];
+		for y in -border .. self.size() + border {
+			for x in -border .. self.size() + border {
+				if self.get_module(x, y) {
+					let px0 = usize::try_from((x + border) * scale).unwrap();
+					let py0 = usize::try_from((y + border) * scale).unwrap();
+					for dy in 0 .. usize::try_from(scale).unwrap() {
+						for dx in 0 .. usize::try_from(scale).unwrap() {
+							pixels[(py0 + dy) * width + (px0 + dx)] = 0;
+						}
+					}
+				}
+			}
+		}
+		(pixels, width)
+	}
+
+
+	/// Renders this QR Code as a standalone 8-bit grayscale PNG file (dark = 0, light = 255),
+	/// at the given border width and integer upscaling factor.
+	///
+	/// Encodes without any compression library: the DEFLATE stream is written as uncompressed
+	/// "stored" blocks, which keeps this dependency-free at the cost of a larger file than a
+	/// real compressor would produce. Fine for the small, highly repetitive images a QR Code is.
+	///
+	/// Panics if `border` is negative or `scale` is zero.
+	pub fn to_png(&self, border: i32, scale: i32) -> alloc::vec::Vec<u8> {
+		let (pixels, width) = self.to_raster(border, scale);
+
+		// Build the raw image data: one filter-type byte (0 = None) followed by `width` grayscale bytes, per row
+		let mut raw = alloc::vec::Vec::with_capacity((width + 1) * width);
+		for row in pixels.chunks(width) {
+			raw.push(0u8);
+			raw.extend_from_slice(row);
+		}
+
+		let mut png = alloc::vec::Vec::new();
+		png.extend_from_slice(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]);
+
+		let mut ihdr = alloc::vec::Vec::with_capacity(13);
+		ihdr.extend_from_slice(&(width as u32).to_be_bytes());
+		ihdr.extend_from_slice(&(width as u32).to_be_bytes());
+		ihdr.push(8);  // Bit depth
+		ihdr.push(0);  // Color type: grayscale
+		ihdr.push(0);  // Compression method: DEFLATE
+		ihdr.push(0);  // Filter method
+		ihdr.push(0);  // Interlace method: none
+		write_png_chunk(&mut png, b"IHDR", &ihdr);
+
+		write_png_chunk(&mut png, b"IDAT", &zlib_store_uncompressed(&raw));
+		write_png_chunk(&mut png, b"IEND", &[]);
+		png
+	}
+
+
+	/// Renders this QR Code to an in-memory 8-bit grayscale image, using the `image` crate's
+	/// `GrayImage` (a `Luma<u8>` pixel buffer) instead of this crate's own bare `to_raster()`
+	/// bytes, for callers who want to resize, recolor, or save the result via `image`'s own
+	/// encoders rather than this crate's dependency-free `to_png()`.
+	///
+	/// Panics if `border` is negative or `scale` is zero.
+	#[cfg(feature = "image")]
+	pub fn to_image(&self, border: i32, scale: i32) -> image::GrayImage {
+		let (pixels, width) = self.to_raster(border, scale);
+		image::GrayImage::from_raw(width as u32, width as u32, pixels)
+			.expect("to_raster() always returns width*width bytes")
+	}
+
+}
+
+
+// Wraps `data` in a minimal zlib stream (RFC 1950) using uncompressed ("stored") DEFLATE
+// blocks (RFC 1951 section 3.2.4), split into chunks no larger than 65535 bytes each.
+fn zlib_store_uncompressed(data: &[u8]) -> alloc::vec::Vec<u8> {
+	let mut result = alloc::vec::Vec::with_capacity(data.len() + data.len() / 65535 * 5 + 11);
+	result.push(0x78);  // CMF: DEFLATE, 32K window
+	result.push(0x01);  // FLG: no preset dictionary, check bits for (CMF*256+FLG) % 31 == 0
+
+	if data.is_empty() {
+		result.push(0x01);  // Final empty stored block
+		result.extend_from_slice(&0u16.to_le_bytes());
+		result.extend_from_slice(&0xFFFFu16.to_le_bytes());
+	} else {
+		let mut offset = 0;
+		while offset < data.len() {
+			let len = core::cmp::min(65535, data.len() - offset);
+			let is_final = offset + len == data.len();
+			result.push(u8::from(is_final));
+			let len = len as u16;
+			result.extend_from_slice(&len.to_le_bytes());
+			result.extend_from_slice(&(!len).to_le_bytes());
+			result.extend_from_slice(&data[offset .. offset + usize::from(len)]);
+			offset += usize::from(len);
+		}
+	}
+
+	result.extend_from_slice(&adler32(data).to_be_bytes());
+	result
+}
+
+
+fn adler32(data: &[u8]) -> u32 {
+	let mut a: u32 = 1;
+	let mut b: u32 = 0;
+	for &byte in data {
+		a = (a + u32::from(byte)) % 65521;
+		b = (b + a) % 65521;
+	}
+	(b << 16) | a
+}
+
+
+fn crc32(data: &[u8]) -> u32 {
+	let mut crc: u32 = 0xFFFFFFFF;
+	for &byte in data {
+		crc ^= u32::from(byte);
+		for _ in 0 .. 8 {
+			crc = (crc >> 1) ^ ((crc & 1) * 0xEDB88320);
+		}
+	}
+	!crc
+}
+
+
+fn write_png_chunk(out: &mut alloc::vec::Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+	out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+	let start = out.len();
+	out.extend_from_slice(chunk_type);
+	out.extend_from_slice(data);
+	out.extend_from_slice(&crc32(&out[start ..]).to_be_bytes());
+}
+
+
+/// A chainable builder returned by `QrCode::render()`, for configuring the quiet zone, colors,
+/// and inversion of the SVG and Unicode-text backends without repeating their full argument
+/// lists at every call site.
+pub struct QrCodeRenderer<'a> {
+	qr: &'a QrCode,
+	border: i32,
+	dark: String,
+	light: String,
+	invert: bool,
+}
+
+impl<'a> QrCodeRenderer<'a> {
+
+	/// Sets the quiet zone (border) width in modules. Default is 4, the minimum recommended
+	/// by the standard. Panics (when rendered) if negative.
+	pub fn quiet_zone(mut self, modules: i32) -> Self {
+		self.border = modules;
+		self
+	}
+
+	/// Sets the SVG fill color used for dark modules. Default is `"#000000"`.
+	pub fn dark_color(mut self, color: &str) -> Self {
+		self.dark = String::from(color);
+		self
+	}
+
+	/// Sets the SVG fill color used for light modules. Default is `"#FFFFFF"`.
+	pub fn light_color(mut self, color: &str) -> Self {
+		self.light = String::from(color);
+		self
+	}
+
+	/// Swaps dark and light colors (for SVG) or glyphs (for Unicode text), for light-on-dark
+	/// terminals and displays. Default is `false`.
+	pub fn invert(mut self, invert: bool) -> Self {
+		self.invert = invert;
+		self
+	}
+
+	/// Renders to an SVG string, honoring the configured quiet zone, colors, and inversion.
+	pub fn to_svg_string(&self) -> String {
+		if self.invert {
+			self.qr.to_svg_string(self.border, &self.light, &self.dark)
+		} else {
+			self.qr.to_svg_string(self.border, &self.dark, &self.light)
+		}
+	}
+
+	/// Renders to a half-block Unicode terminal string, honoring the configured quiet zone
+	/// and inversion. Colors set via `dark_color()`/`light_color()` do not apply here, since
+	/// terminal glyphs have no color of their own.
+	pub fn to_unicode_string(&self) -> String {
+		assert!(self.border >= 0, "Border must be non-negative");
+		let border = self.border;
+		let top = -border;
+		let bottom = self.qr.size() + border;
+		let mut result = String::new();
+		let mut y = top;
+		while y < bottom {
+			for x in -border .. self.qr.size() + border {
+				let mut upper = self.qr.get_module(x, y);
+				let mut lower = y + 1 < bottom && self.qr.get_module(x, y + 1);
+				if self.invert {
+					upper = !upper;
+					lower = !lower;
+				}
+				result.push(match (upper, lower) {
+					(false, false) => ' ',
+					(false, true ) => '▄',
+					(true , false) => '▀',
+					(true , true ) => '█',
+				});
+			}
+			result.push('\n');
+			y += 2;
+		}
+		result
+	}
+
+}